@@ -0,0 +1,111 @@
+//! TOML config file support, currently just the `[temp_filter]` table.
+//!
+//! The filter design mirrors bottom's: either an ignore-list (hide sensors
+//! matching `list`) or an allow-list (keep only sensors matching `list`).
+
+use std::fs;
+use std::path::Path;
+
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+/// Raw `[temp_filter]` section as read from the config file.
+#[derive(Debug, Deserialize, Default)]
+pub struct TempFilterConfig {
+    #[serde(default)]
+    pub is_list_ignored: bool,
+    #[serde(default)]
+    pub list: Vec<String>,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Config {
+    temp_filter: Option<TempFilterConfig>,
+}
+
+/// A compiled sensor filter, built once at startup so the polling loop
+/// isn't recompiling regexes every tick.
+pub struct Filter {
+    is_list_ignored: bool,
+    patterns: Vec<Regex>,
+}
+
+impl Filter {
+    /// Compile a `TempFilterConfig` into matchable patterns.
+    pub fn new(config: &TempFilterConfig) -> Filter {
+        let patterns = config
+            .list
+            .iter()
+            .filter_map(|pattern| compile_pattern(pattern, config))
+            .collect();
+
+        Filter {
+            is_list_ignored: config.is_list_ignored,
+            patterns,
+        }
+    }
+
+    /// An empty filter that keeps every sensor, used when there's no config
+    /// file (or no `[temp_filter]` table) to read.
+    pub fn keep_all() -> Filter {
+        Filter {
+            is_list_ignored: true,
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Whether a sensor with this label should be kept.
+    pub fn keep(&self, label: &str) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+
+        let matched = self.patterns.iter().any(|pattern| pattern.is_match(label));
+        if self.is_list_ignored {
+            !matched
+        } else {
+            matched
+        }
+    }
+}
+
+/// Turn one `list` entry into a compiled, case/whole-word aware regex.
+/// Non-regex patterns are escaped so they match literally.
+fn compile_pattern(pattern: &str, config: &TempFilterConfig) -> Option<Regex> {
+    let pattern = if config.regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    let pattern = if config.whole_word {
+        format!("^(?:{})$", pattern)
+    } else {
+        pattern
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!config.case_sensitive)
+        .build()
+        .ok()
+}
+
+/// Load the `[temp_filter]` table from a TOML file at `path`.
+/// A missing file, unreadable file, or missing table all fall back to
+/// keeping every sensor.
+pub fn load_filter(path: &Path) -> Filter {
+    let config: Config = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default();
+
+    match config.temp_filter {
+        Some(temp_filter) => Filter::new(&temp_filter),
+        None => Filter::keep_all(),
+    }
+}
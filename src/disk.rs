@@ -0,0 +1,26 @@
+//! Disk usage collection, following heim's streaming partition API.
+
+use futures::stream::StreamExt;
+use heim::disk;
+use heim::units::information::byte;
+
+/// Percentage of disk space in use, summed across every physical
+/// partition. `None` when no partition could be read at all.
+pub async fn disk_usage() -> Option<f64> {
+    let mut partitions = disk::partitions_physical();
+    let mut used = 0u64;
+    let mut total = 0u64;
+
+    while let Some(Ok(partition)) = partitions.next().await {
+        if let Ok(usage) = disk::usage(partition.mount_point().to_path_buf()).await {
+            used += usage.used().get::<byte>();
+            total += usage.total().get::<byte>();
+        }
+    }
+
+    if total == 0 {
+        None
+    } else {
+        Some(used as f64 / total as f64 * 100.0)
+    }
+}
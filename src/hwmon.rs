@@ -0,0 +1,184 @@
+//! Direct hwmon sysfs reader for thermal sensors.
+//!
+//! This replaces the `heim::sensors` path: we walk `/sys/class/hwmon/hwmonN`
+//! ourselves, which drops a dependency layer and gives us the max/critical
+//! thresholds heim doesn't expose alongside the current reading.
+
+use std::fs;
+use std::path::Path;
+
+use crate::config::Filter;
+
+/// Chip names known to carry the Raspberry Pi / SoC CPU temperature.
+/// Preferring these is more reliable than guessing from a "Composite"/"CPU"
+/// sensor label, which varies across boards.
+const KNOWN_CPU_CHIPS: &[&str] = &["cpu_thermal", "rp1_adc", "bcm2835_thermal"];
+
+/// A single `tempN_*` reading from one hwmon chip, in degrees Celsius.
+#[derive(Debug, Clone)]
+pub struct SensorReading {
+    pub name: String,
+    pub current: f64,
+    pub max: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+impl SensorReading {
+    /// How close, in degrees, `current` is allowed to get to `critical`
+    /// before we consider the sensor in danger of throttling/shutdown.
+    const CRITICAL_MARGIN: f64 = 5.0;
+
+    /// Whether this reading is within `CRITICAL_MARGIN` degrees of the
+    /// chip's critical threshold, if it reports one.
+    pub fn is_near_critical(&self) -> bool {
+        self.critical.map_or(false, |critical| {
+            self.current >= critical - Self::CRITICAL_MARGIN
+        })
+    }
+}
+
+/// Read every temperature input under every hwmon chip whose name passes
+/// `filter`.
+///
+/// Returns one `SensorReading` per `tempN_input` file found, one entry per
+/// chip/sensor pair, keyed by the chip's `name` file.
+pub fn read_sensors(filter: &Filter) -> Vec<SensorReading> {
+    let hwmon_root = Path::new("/sys/class/hwmon");
+
+    let entries = match fs::read_dir(hwmon_root) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .flatten()
+        .flat_map(|entry| {
+            let chip_dir = entry.path();
+
+            // Computed once per device: skip chips whose underlying device is
+            // suspended (D3cold) so we don't wake a power-managed NVMe/disk
+            // just to read its temperature.
+            if is_asleep(&chip_dir) {
+                return Vec::new();
+            }
+
+            let chip_name = fs::read_to_string(chip_dir.join("name"))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| String::from("unknown"));
+
+            if !filter.keep(&chip_name) {
+                return Vec::new();
+            }
+
+            read_chip_temperatures(&chip_dir, &chip_name)
+        })
+        .collect()
+}
+
+/// Whether the device backing this hwmon chip is in a low-power (D3cold)
+/// runtime state and should be left alone rather than read.
+fn is_asleep(chip_dir: &Path) -> bool {
+    let device_dir = chip_dir.join("device");
+
+    for candidate in ["power_state", "power/runtime_status"] {
+        if let Ok(state) = fs::read_to_string(device_dir.join(candidate)) {
+            let state = state.trim();
+            return state.eq_ignore_ascii_case("D3cold") || state.eq_ignore_ascii_case("suspended");
+        }
+    }
+
+    // No readable power state: assume the device is awake.
+    false
+}
+
+/// How to reduce several sensor readings down to a single CPU temperature.
+#[derive(Debug, Clone, Copy)]
+pub enum TempMode {
+    /// A single known Pi/SoC chip reading (the historical behavior).
+    Composite,
+    /// The single hottest reading among the known Pi/SoC chips, useful once
+    /// a board exposes more than one core cluster's sensor.
+    Hottest,
+    /// The average of every known Pi/SoC chip reading, for SoCs that split
+    /// CPU temperature across multiple core clusters (e.g. pACC/eACC).
+    Average,
+}
+
+/// Reduce the collected sensor readings to a single CPU temperature using
+/// `mode`. Falls back to `None` when there's nothing to report.
+pub fn select(readings: &[SensorReading], mode: TempMode) -> Option<SensorReading> {
+    let known: Vec<&SensorReading> = readings
+        .iter()
+        .filter(|reading| KNOWN_CPU_CHIPS.contains(&reading.name.as_str()))
+        .collect();
+    let candidates = if known.is_empty() {
+        readings.iter().collect::<Vec<_>>()
+    } else {
+        known
+    };
+
+    match mode {
+        TempMode::Composite => candidates.first().map(|reading| (*reading).clone()),
+        TempMode::Hottest => candidates
+            .into_iter()
+            .max_by(|a, b| a.current.partial_cmp(&b.current).unwrap())
+            .cloned(),
+        TempMode::Average => {
+            if candidates.is_empty() {
+                return None;
+            }
+            let sum: f64 = candidates.iter().map(|reading| reading.current).sum();
+            Some(SensorReading {
+                name: String::from("average"),
+                current: sum / candidates.len() as f64,
+                max: None,
+                critical: None,
+            })
+        }
+    }
+}
+
+fn read_chip_temperatures(chip_dir: &Path, chip_name: &str) -> Vec<SensorReading> {
+    let entries = match fs::read_dir(chip_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut indices: Vec<u32> = entries
+        .flatten()
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("temp")?
+                .strip_suffix("_input")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    indices.sort_unstable();
+
+    indices
+        .into_iter()
+        .filter_map(|index| {
+            read_millidegrees(chip_dir, index, "input").map(|current| SensorReading {
+                name: chip_name.to_string(),
+                current,
+                max: read_millidegrees(chip_dir, index, "max"),
+                critical: read_millidegrees(chip_dir, index, "crit"),
+            })
+        })
+        .collect()
+}
+
+/// Read a `tempN_<suffix>` file (millidegrees Celsius) and convert it to
+/// degrees Celsius.
+fn read_millidegrees(chip_dir: &Path, index: u32, suffix: &str) -> Option<f64> {
+    let path = chip_dir.join(format!("temp{}_{}", index, suffix));
+    fs::read_to_string(path)
+        .ok()?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|millidegrees| millidegrees / 1000.0)
+}
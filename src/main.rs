@@ -1,74 +1,240 @@
-use std::collections::HashMap;
 use std::fmt;
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
 
-use futures::stream::StreamExt; // for `next` on streams.
-use heim::{cpu, memory, sensors, units, Result};
+use heim::{cpu, memory, units, Result};
 use tokio::time::{sleep, Duration};
 
+mod config;
+mod disk;
+mod hwmon;
+mod network;
+
+/// Whether `flag` was passed on the command line, for simple on/off
+/// switches like `--no-disk`.
+fn flag_present(flag: &str) -> bool {
+    std::env::args().any(|arg| arg == flag)
+}
+
+/// Default location of the TOML config file, relative to the working
+/// directory the tool is started from.
+const DEFAULT_CONFIG_PATH: &str = "pi_stats.toml";
+
+/// Parse the `--config` flag out of the process arguments, defaulting to
+/// `DEFAULT_CONFIG_PATH` when it's absent.
+fn parse_config_path() -> PathBuf {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            if let Some(value) = args.next() {
+                return PathBuf::from(value);
+            }
+        }
+    }
+    PathBuf::from(DEFAULT_CONFIG_PATH)
+}
+
+/// Which unit to report the CPU temperature in.
+#[derive(Debug, Clone, Copy)]
+enum TemperatureType {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl FromStr for TemperatureType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "c" | "celsius" => Ok(TemperatureType::Celsius),
+            "f" | "fahrenheit" => Ok(TemperatureType::Fahrenheit),
+            "k" | "kelvin" => Ok(TemperatureType::Kelvin),
+            other => Err(format!("unknown temperature type: {}", other)),
+        }
+    }
+}
+
+/// Parse the `--temp-type` flag out of the process arguments, defaulting to
+/// Celsius when it's absent or unrecognized.
+fn parse_temperature_type() -> TemperatureType {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--temp-type" {
+            if let Some(value) = args.next() {
+                if let Ok(temp_type) = value.parse() {
+                    return temp_type;
+                }
+            }
+        }
+    }
+    TemperatureType::Celsius
+}
+
+impl FromStr for hwmon::TempMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "composite" => Ok(hwmon::TempMode::Composite),
+            "hottest" => Ok(hwmon::TempMode::Hottest),
+            "average" => Ok(hwmon::TempMode::Average),
+            other => Err(format!("unknown temperature mode: {}", other)),
+        }
+    }
+}
+
+/// Parse the `--temp-mode` flag out of the process arguments, defaulting to
+/// `Composite` (the historical single-sensor behavior) when it's absent or
+/// unrecognized.
+fn parse_temp_mode() -> hwmon::TempMode {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--temp-mode" {
+            if let Some(value) = args.next() {
+                if let Ok(temp_mode) = value.parse() {
+                    return temp_mode;
+                }
+            }
+        }
+    }
+    hwmon::TempMode::Composite
+}
+
 /// Hardware stats: cpu frequency, temperature and available RAM.
 #[derive(Debug)]
 struct PiStats {
     /// CPU frequency, core average presumably.
     cpu_frequency: units::Frequency,
-    /// This is the composite, or 'CPU' temperature
-    temperature: units::ThermodynamicTemperature,
+    /// The preferred CPU/SoC thermal sensor reading.
+    temperature: hwmon::SensorReading,
+    /// Unit the temperature should be displayed in.
+    temperature_type: TemperatureType,
     /// Available memory
     /// Note that this is different from 'free' memory in that this
     /// takes into account disk cache and buffers that the OS will
     /// reclaim under pressure.
     memory_available: units::Information,
+    /// Percentage of disk space in use, summed across every physical
+    /// partition. `None` when `--no-disk` was passed.
+    disk_usage: Option<f64>,
+    /// (down, up) network throughput in bytes/sec since the last tick.
+    /// `None` when `--no-network` was passed.
+    network_throughput: Option<(f64, f64)>,
 }
 
 impl fmt::Display for PiStats {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let celsius = units::ThermodynamicTemperature::new::<
+            units::thermodynamic_temperature::degree_celsius,
+        >(self.temperature.current);
+
+        let (temperature, unit_label) = match self.temperature_type {
+            TemperatureType::Celsius => (
+                celsius.get::<units::thermodynamic_temperature::degree_celsius>(),
+                "C",
+            ),
+            TemperatureType::Fahrenheit => (
+                celsius.get::<units::thermodynamic_temperature::degree_fahrenheit>(),
+                "F",
+            ),
+            TemperatureType::Kelvin => (
+                celsius.get::<units::thermodynamic_temperature::kelvin>(),
+                "K",
+            ),
+        };
+
         write!(
             f,
-            "{} Mhz / {} C / {} MiB",
+            "{} Mhz / {} {}{} / {} MiB",
             self.cpu_frequency.get::<units::frequency::megahertz>(),
-            self.temperature
-                .get::<units::thermodynamic_temperature::degree_celsius>(),
+            temperature,
+            unit_label,
+            if self.temperature.is_near_critical() {
+                " !"
+            } else {
+                ""
+            },
             self.memory_available.get::<units::information::mebibyte>()
-        )
+        )?;
+
+        if let Some(disk_usage) = self.disk_usage {
+            write!(f, " / {:.0}% disk", disk_usage)?;
+        }
+
+        if let Some((down, up)) = self.network_throughput {
+            write!(
+                f,
+                " / {} down / {} up",
+                format_rate(down),
+                format_rate(up)
+            )?;
+        }
+
+        Ok(())
     }
 }
 
-/// Get the CPU temperature.
-/// This guesses a little about which sensor is appropriate, we pick
-/// Composite preferentially, and CPU which we know works on the
-/// Raspberry Pi.
-async fn cpu_temperature() -> units::ThermodynamicTemperature {
-    // We stuff all the sensors into a hashmap, then pull out our
-    // preferred sensors by label names.
-    let composite_label = String::from("Composite");
-    let cpu_label = String::from("CPU");
-    let temp_default = units::ThermodynamicTemperature::new::<
-        units::thermodynamic_temperature::degree_celsius,
-    >(0.0);
-
-    let mut temperature_sensors = HashMap::new();
-
-    let mut sensors = sensors::temperatures();
-    while let Some(Ok(sensor)) = sensors.next().await {
-        temperature_sensors.insert(
-            String::from(sensor.label().unwrap_or("unknown")),
-            sensor.current(),
-        );
-    }
+/// Format a bytes/sec rate as MiB/s, the unit that fits a Pi's typical
+/// network speeds on a single console line.
+fn format_rate(bytes_per_sec: f64) -> String {
+    format!("{:.1} MiB/s", bytes_per_sec / 1024.0 / 1024.0)
+}
 
-    *temperature_sensors
-        .get(&composite_label)
-        .ok_or(temperature_sensors.get(&cpu_label))
-        .unwrap_or(&temp_default)
+/// Get the CPU temperature by reading hwmon sysfs directly, preferring a
+/// known Pi/SoC chip over guessing from a "Composite"/"CPU" label, and
+/// reducing multiple matching sensors down using `mode`.
+async fn cpu_temperature(filter: &config::Filter, mode: hwmon::TempMode) -> hwmon::SensorReading {
+    let readings = hwmon::read_sensors(filter);
+    hwmon::select(&readings, mode).unwrap_or(hwmon::SensorReading {
+        name: String::from("unknown"),
+        current: 0.0,
+        max: None,
+        critical: None,
+    })
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let temperature_type = parse_temperature_type();
+    let temp_mode = parse_temp_mode();
+    let sensor_filter = config::load_filter(&parse_config_path());
+    let disk_enabled = !flag_present("--no-disk");
+    let network_enabled = !flag_present("--no-network");
+    let mut network_monitor = network::NetworkMonitor::new();
+
     loop {
+        // Collect the tick's metrics concurrently rather than one after
+        // another, so the loop's latency is the slowest collector rather
+        // than the sum of all of them.
+        let (frequency, temperature, memory, disk_usage, network_throughput) = tokio::join!(
+            cpu::frequency(),
+            cpu_temperature(&sensor_filter, temp_mode),
+            memory::memory(),
+            async {
+                if disk_enabled {
+                    disk::disk_usage().await
+                } else {
+                    None
+                }
+            },
+            async {
+                if network_enabled {
+                    Some(network_monitor.throughput().await)
+                } else {
+                    None
+                }
+            }
+        );
+
         let hardware_status = PiStats {
-            cpu_frequency: cpu::frequency().await?.current(),
-            temperature: cpu_temperature().await,
-            memory_available: memory::memory().await?.available(),
+            cpu_frequency: frequency?.current(),
+            temperature,
+            temperature_type,
+            memory_available: memory?.available(),
+            disk_usage,
+            network_throughput,
         };
 
         // Clear line, print the hardware stats, return to start of line.
@@ -0,0 +1,56 @@
+//! Network throughput collection.
+//!
+//! `heim::net` only exposes cumulative byte counters, so we keep the
+//! previous sample and the elapsed time between ticks to turn them into a
+//! bytes/sec rate.
+
+use std::time::Instant;
+
+use futures::stream::StreamExt;
+use heim::net;
+use heim::units::information::byte;
+
+/// Tracks the previous sample so successive calls can report a rate
+/// instead of a cumulative total.
+pub struct NetworkMonitor {
+    previous: Option<(Instant, u64, u64)>,
+}
+
+impl NetworkMonitor {
+    pub fn new() -> NetworkMonitor {
+        NetworkMonitor { previous: None }
+    }
+
+    /// Sample total received/transmitted bytes across every interface and
+    /// return the (down, up) rate in bytes/sec since the last sample. The
+    /// first call has nothing to compare against, so it reports `(0.0, 0.0)`.
+    pub async fn throughput(&mut self) -> (f64, f64) {
+        let mut counters = net::io_counters();
+        let mut received = 0u64;
+        let mut transmitted = 0u64;
+
+        while let Some(Ok(counter)) = counters.next().await {
+            received += counter.bytes_recv().get::<byte>();
+            transmitted += counter.bytes_sent().get::<byte>();
+        }
+
+        let now = Instant::now();
+        let rate = match self.previous {
+            Some((previous_time, previous_received, previous_transmitted)) => {
+                let elapsed = now.duration_since(previous_time).as_secs_f64();
+                if elapsed > 0.0 {
+                    (
+                        received.saturating_sub(previous_received) as f64 / elapsed,
+                        transmitted.saturating_sub(previous_transmitted) as f64 / elapsed,
+                    )
+                } else {
+                    (0.0, 0.0)
+                }
+            }
+            None => (0.0, 0.0),
+        };
+
+        self.previous = Some((now, received, transmitted));
+        rate
+    }
+}